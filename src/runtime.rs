@@ -5,19 +5,43 @@ use crate::{
     term::{Unit, Value, ValueKind},
 };
 
-use bigdecimal::{BigDecimal, ToPrimitive};
+use bigdecimal::{BigDecimal, FromPrimitive, RoundingMode, ToPrimitive};
+use num_rational::Ratio;
 use thiserror::Error;
 
+/// A native function exposed under a name in [`Runtime::builtins`], consulted
+/// before user-defined `functions` when evaluating a call.
+type BuiltinFn = fn(FC, &[Value]) -> Result<Value, EvalError>;
+
+/// Default for [`Runtime::max_depth`], borrowed from rhai's "maximum level of
+/// nesting" safeguard against stack overflow on recursive definitions.
+const DEFAULT_MAX_DEPTH: usize = 256;
+
 #[derive(Default)]
 pub struct Runtime {
     units: HashSet<Name>,
     variables: HashMap<Name, Value>,
     functions: HashMap<Name, (Vec<Name>, Expression)>,
+    builtins: HashMap<Name, BuiltinFn>,
+    max_depth: usize,
 }
 
 impl Runtime {
     pub fn new() -> Self {
-        Default::default()
+        Self {
+            builtins: builtin_functions(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            ..Default::default()
+        }
+    }
+
+    /// Like [`Runtime::new`], but with a custom limit on expression and call
+    /// nesting depth, instead of the [`DEFAULT_MAX_DEPTH`].
+    pub fn with_max_depth(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            ..Self::new()
+        }
     }
 
     pub fn eval_line_item(&mut self, item: LineItem) -> Result<EvalResult, ItemError> {
@@ -36,7 +60,8 @@ impl Runtime {
 
                 let is_defined = self.units.contains(name)
                     || self.variables.contains_key(name)
-                    || self.functions.contains_key(name);
+                    || self.functions.contains_key(name)
+                    || self.builtins.contains_key(name);
 
                 if is_defined {
                     decl.into_expression()
@@ -64,10 +89,10 @@ impl Runtime {
                     Ok(EvalResult::Empty)
                 }
             }
-            Item::VariableDeclaration { fc: _, name, rhs } => {
-                let value = self.eval_expr(&rhs)?;
-
+            Item::VariableDeclaration { fc, name, rhs } => {
                 let name = name.name();
+                let value = self.solve_for(fc, &name, &rhs)?;
+
                 match self.variables.entry(name.clone()) {
                     Entry::Occupied(_) => Err(ItemError::VariableRedefined(name)),
                     Entry::Vacant(entry) => {
@@ -84,6 +109,10 @@ impl Runtime {
             } => {
                 let name = name.name();
 
+                if self.builtins.contains_key(&name) {
+                    return Err(ItemError::FunctionRedefined(name));
+                }
+
                 match self.functions.entry(name.clone()) {
                     Entry::Occupied(_) => Err(ItemError::FunctionRedefined(name)),
                     Entry::Vacant(entry) => {
@@ -104,6 +133,23 @@ impl Runtime {
     }
 
     fn eval_expr(&self, expr: &Expression) -> Result<Value, EvalError> {
+        self.eval_expr_scoped(expr, &HashMap::new(), 0)
+    }
+
+    /// Evaluates `expr` with `bindings` consulted before globals, so that
+    /// function parameters can shadow variables and units of the same name.
+    /// `depth` counts nested expressions and calls, guarding against a stack
+    /// overflow on deeply nested expressions or runaway recursive functions.
+    fn eval_expr_scoped(
+        &self,
+        expr: &Expression,
+        bindings: &HashMap<&Name, Value>,
+        depth: usize,
+    ) -> Result<Value, EvalError> {
+        if depth > self.max_depth {
+            return Err(EvalError::RecursionLimitExceeded(expr_fc(expr)));
+        }
+
         match expr {
             Expression::IntegerLit { fc: _, val } => Ok(Value {
                 kind: ValueKind::Number(val.clone()),
@@ -129,22 +175,18 @@ impl Runtime {
                     Err(EvalError::UndefinedName(*fc, full_name.clone()))
                 }
             }
-            Expression::Variable(id) => self
-                .lookup(id.name_ref())
+            Expression::Variable(id) => bindings
+                .get(id.name_ref())
+                .cloned()
+                .or_else(|| self.lookup(id.name_ref()))
                 .ok_or_else(|| EvalError::UndefinedName(id.fc(), id.name_ref().clone())),
-            Expression::Call {
-                fc: _,
-                base: _,
-                args: _,
-            } => {
-                todo!()
-            }
+            Expression::Call { fc, base, args } => self.eval_call(*fc, base, args, bindings, depth),
             Expression::PrefixOp { fc, op, expr } => {
-                let mut val = self.eval_expr(expr)?;
+                let mut val = self.eval_expr_scoped(expr, bindings, depth + 1)?;
                 match op {
                     crate::syntax::PrefixOp::Pos => match &mut val.kind {
                         ValueKind::Number(_) => Ok(val),
-                        ValueKind::FunctionRef(_) => {
+                        ValueKind::FunctionRef(_) | ValueKind::Bool(_) => {
                             Err(EvalError::InvalidPrefixOperator(*fc, *op, val))
                         }
                     },
@@ -153,24 +195,78 @@ impl Runtime {
                             *num = -&*num;
                             Ok(val)
                         }
-                        ValueKind::FunctionRef(_) => {
+                        ValueKind::FunctionRef(_) | ValueKind::Bool(_) => {
                             Err(EvalError::InvalidPrefixOperator(*fc, *op, val))
                         }
                     },
                 }
             }
-            Expression::InfixOp { fc, op, lhs, rhs } => self.eval_infix_op(*fc, *op, lhs, rhs),
+            Expression::InfixOp { fc, op, lhs, rhs } => {
+                self.eval_infix_op(*fc, *op, lhs, rhs, bindings, depth + 1)
+            }
             Expression::UnitOf(_, expr) => {
-                let val = self.eval_expr(expr)?;
+                let val = self.eval_expr_scoped(expr, bindings, depth + 1)?;
                 Ok(Value {
                     kind: ValueKind::Number(BigDecimal::from(1)),
                     unit: val.unit,
                 })
             }
-            Expression::Parenthesised(_, expr) => self.eval_expr(expr),
+            Expression::Parenthesised(_, expr) => self.eval_expr_scoped(expr, bindings, depth + 1),
         }
     }
 
+    /// Applies a builtin or user-defined function: `base` must evaluate to a
+    /// `ValueKind::FunctionRef`, either directly or via a bare `Variable`
+    /// naming it. Arguments are evaluated in the caller's scope. A builtin of
+    /// that name is called first with the evaluated arguments; otherwise the
+    /// user-defined body is evaluated in a fresh scope where only the
+    /// parameters are bound (function bodies do not close over the caller's
+    /// locals).
+    fn eval_call(
+        &self,
+        fc: FC,
+        base: &Expression,
+        args: &[Expression],
+        bindings: &HashMap<&Name, Value>,
+        depth: usize,
+    ) -> Result<Value, EvalError> {
+        let callee = self.eval_expr_scoped(base, bindings, depth)?;
+        let name = match callee.kind {
+            ValueKind::FunctionRef(name) => name,
+            _ => return Err(EvalError::NotCallable(fc, callee)),
+        };
+
+        let mut arg_values = Vec::with_capacity(args.len());
+        for arg in args {
+            arg_values.push(self.eval_expr_scoped(arg, bindings, depth)?);
+        }
+
+        if let Some(builtin) = self.builtins.get(&name) {
+            return builtin(fc, &arg_values);
+        }
+
+        let (params, body) = self
+            .functions
+            .get(&name)
+            .ok_or_else(|| EvalError::UndefinedName(fc, name.clone()))?;
+
+        if params.len() != arg_values.len() {
+            return Err(EvalError::ArityMismatch(
+                fc,
+                name.clone(),
+                params.len(),
+                arg_values.len(),
+            ));
+        }
+
+        let mut call_bindings = HashMap::with_capacity(params.len());
+        for (param, val) in params.iter().zip(arg_values) {
+            call_bindings.insert(param, val);
+        }
+
+        self.eval_expr_scoped(body, &call_bindings, depth + 1)
+    }
+
     pub fn lookup(&self, name: &Name) -> Option<Value> {
         if let Some(val) = self.variables.get(name) {
             Some(val.clone())
@@ -179,7 +275,7 @@ impl Runtime {
                 kind: ValueKind::Number(BigDecimal::from(1)),
                 unit: Unit::new_named(name.clone()),
             })
-        } else if self.functions.contains_key(name) {
+        } else if self.functions.contains_key(name) || self.builtins.contains_key(name) {
             Some(Value {
                 kind: ValueKind::FunctionRef(name.clone()),
                 unit: Unit::new(),
@@ -195,9 +291,11 @@ impl Runtime {
         op: InfixOp,
         lhs: &Expression,
         rhs: &Expression,
+        bindings: &HashMap<&Name, Value>,
+        depth: usize,
     ) -> Result<Value, EvalError> {
-        let lhs = self.eval_expr(lhs)?;
-        let rhs = self.eval_expr(rhs)?;
+        let lhs = self.eval_expr_scoped(lhs, bindings, depth)?;
+        let rhs = self.eval_expr_scoped(rhs, bindings, depth)?;
 
         let unit = infix_unit(fc, op, &lhs, &rhs)?;
 
@@ -222,42 +320,256 @@ impl Runtime {
                 kind: ValueKind::Number(a % b),
                 unit,
             }),
-            (InfixOp::Pow, ValueKind::Number(a), ValueKind::Number(b)) => {
-                let pow: isize = if b.is_integer() {
-                    b.to_isize().unwrap()
-                } else {
-                    unimplemented!("Floating point power is not implemented")
-                };
-
-                let mut res = BigDecimal::from(1);
+            (InfixOp::Pow, ValueKind::Number(a), ValueKind::Number(b)) => Ok(Value {
+                kind: ValueKind::Number(pow_numeric(fc, a, b)?),
+                unit,
+            }),
+            (InfixOp::Eq, ValueKind::Number(a), ValueKind::Number(b)) => Ok(Value {
+                kind: ValueKind::Bool(a == b),
+                unit,
+            }),
+            (InfixOp::Neq, ValueKind::Number(a), ValueKind::Number(b)) => Ok(Value {
+                kind: ValueKind::Bool(a != b),
+                unit,
+            }),
+            (InfixOp::Gt, ValueKind::Number(a), ValueKind::Number(b)) => Ok(Value {
+                kind: ValueKind::Bool(a > b),
+                unit,
+            }),
+            (op, _, _) => Err(EvalError::InvalidInfixOperator(fc, op, lhs, rhs)),
+        }
+    }
 
-                for _ in 0..pow.abs() {
-                    res = res * a;
-                }
+    /// Solves `unknown = rhs` for `unknown`, which may itself appear
+    /// anywhere inside `rhs`. `rhs` is evaluated symbolically into an affine
+    /// form `a*unknown + b`; since the left-hand side is just `1*unknown + 0`,
+    /// the equation reduces to `(1 - a)*unknown = b`, which is solved
+    /// directly. A plain assignment like `x = 5` is the special case where
+    /// `rhs` doesn't mention `unknown` at all (`a = 0`).
+    fn solve_for(&self, fc: FC, unknown: &Name, rhs: &Expression) -> Result<Value, EvalError> {
+        let lhs = Affine::unknown();
+        let rhs = self.eval_affine(rhs, unknown)?;
 
-                if pow.is_negative() {
-                    res = res.inverse();
-                }
+        let coeff = combine_numeric(fc, InfixOp::Sub, &lhs.a, &rhs.a)?;
+        // `lhs.b` is always the dimensionless zero placeholder from
+        // `Affine::unknown()`, not a real unit-bearing value, so `rhs.b` is
+        // `offset` outright; subtracting `lhs.b` through `combine_numeric`
+        // would reject it as a unit mismatch whenever `rhs` carries a unit
+        // (e.g. the ordinary declaration `d = 5 * meter`).
+        let offset = rhs.b;
 
-                Ok(Value {
-                    kind: ValueKind::Number(res),
-                    unit,
-                })
+        match &coeff.kind {
+            ValueKind::Number(n) if n == &BigDecimal::from(0) => {
+                Err(EvalError::NoUniqueSolution(fc))
             }
-            (InfixOp::Eq, ValueKind::Number(_), ValueKind::Number(_)) => {
-                todo!()
+            ValueKind::Number(_) => combine_numeric(fc, InfixOp::Div, &offset, &coeff),
+            ValueKind::FunctionRef(_) | ValueKind::Bool(_) => {
+                unreachable!("affine coefficients are always ValueKind::Number")
             }
-            (InfixOp::Neq, ValueKind::Number(_), ValueKind::Number(_)) => {
-                todo!()
+        }
+    }
+
+    /// Evaluates `expr` into an affine form `a*unknown + b`, where `a` and
+    /// `b` are themselves unit-carrying values. `Add`/`Sub` combine both
+    /// sides componentwise; `Mul`/`Div` only stay linear when one side is a
+    /// plain constant (`a == 0`), and `Pow` only when the base is constant.
+    /// Anything else is rejected as [`EvalError::NonlinearEquation`].
+    /// Sub-expressions that don't contain `unknown` are evaluated normally.
+    fn eval_affine(&self, expr: &Expression, unknown: &Name) -> Result<Affine, EvalError> {
+        match expr {
+            Expression::Variable(id) if id.name_ref() == unknown => Ok(Affine::unknown()),
+            Expression::Parenthesised(_, inner) => self.eval_affine(inner, unknown),
+            Expression::PrefixOp {
+                fc,
+                op,
+                expr: inner,
+            } => {
+                let affine = self.eval_affine(inner, unknown)?;
+                match op {
+                    crate::syntax::PrefixOp::Pos => Ok(affine),
+                    crate::syntax::PrefixOp::Neg => Ok(Affine {
+                        a: negate_value(*fc, affine.a)?,
+                        b: negate_value(*fc, affine.b)?,
+                    }),
+                }
             }
-            (InfixOp::Gt, ValueKind::Number(_), ValueKind::Number(_)) => {
-                todo!()
+            Expression::InfixOp { fc, op, lhs, rhs } => {
+                let lhs = self.eval_affine(lhs, unknown)?;
+                let rhs = self.eval_affine(rhs, unknown)?;
+
+                match op {
+                    InfixOp::Add => Ok(Affine {
+                        a: combine_numeric(*fc, InfixOp::Add, &lhs.a, &rhs.a)?,
+                        b: combine_numeric(*fc, InfixOp::Add, &lhs.b, &rhs.b)?,
+                    }),
+                    InfixOp::Sub => Ok(Affine {
+                        a: combine_numeric(*fc, InfixOp::Sub, &lhs.a, &rhs.a)?,
+                        b: combine_numeric(*fc, InfixOp::Sub, &lhs.b, &rhs.b)?,
+                    }),
+                    InfixOp::Mul => {
+                        if lhs.is_constant() {
+                            scale_affine(*fc, &lhs.b, rhs)
+                        } else if rhs.is_constant() {
+                            scale_affine(*fc, &rhs.b, lhs)
+                        } else {
+                            Err(EvalError::NonlinearEquation(*fc))
+                        }
+                    }
+                    InfixOp::Div => {
+                        if rhs.is_constant() {
+                            scale_affine(*fc, &invert_value(*fc, &rhs.b)?, lhs)
+                        } else {
+                            Err(EvalError::NonlinearEquation(*fc))
+                        }
+                    }
+                    InfixOp::Pow => {
+                        if !lhs.is_constant() || !rhs.is_constant() {
+                            return Err(EvalError::NonlinearEquation(*fc));
+                        }
+
+                        let unit = infix_unit(*fc, InfixOp::Pow, &lhs.b, &rhs.b)?;
+                        let value = match (&lhs.b.kind, &rhs.b.kind) {
+                            (ValueKind::Number(a), ValueKind::Number(b)) => {
+                                ValueKind::Number(pow_numeric(*fc, a, b)?)
+                            }
+                            _ => {
+                                return Err(EvalError::InvalidInfixOperator(
+                                    *fc,
+                                    InfixOp::Pow,
+                                    lhs.b.clone(),
+                                    rhs.b.clone(),
+                                ))
+                            }
+                        };
+
+                        Ok(Affine::constant(Value { kind: value, unit }))
+                    }
+                    InfixOp::Mod | InfixOp::Eq | InfixOp::Neq | InfixOp::Gt => {
+                        Err(EvalError::NonlinearEquation(*fc))
+                    }
+                }
             }
-            (op, _, _) => Err(EvalError::InvalidInfixOperator(fc, op, lhs, rhs)),
+            _ => Ok(Affine::constant(self.eval_expr(expr)?)),
         }
     }
 }
 
+/// The symbolic form `a*unknown + b` tracked while solving an equation for
+/// `unknown`; both coefficients carry their own unit.
+#[derive(Clone)]
+struct Affine {
+    a: Value,
+    b: Value,
+}
+
+impl Affine {
+    fn unknown() -> Self {
+        Affine {
+            a: Value {
+                kind: ValueKind::Number(BigDecimal::from(1)),
+                unit: Unit::new(),
+            },
+            b: Value {
+                kind: ValueKind::Number(BigDecimal::from(0)),
+                unit: Unit::new(),
+            },
+        }
+    }
+
+    fn constant(value: Value) -> Self {
+        Affine {
+            a: Value {
+                kind: ValueKind::Number(BigDecimal::from(0)),
+                unit: Unit::new(),
+            },
+            b: value,
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        matches!(&self.a.kind, ValueKind::Number(n) if n == &BigDecimal::from(0))
+    }
+}
+
+/// Scales both coefficients of `affine` by a constant `factor`, used for the
+/// `Mul`/`Div` cases of [`Runtime::eval_affine`].
+fn scale_affine(fc: FC, factor: &Value, affine: Affine) -> Result<Affine, EvalError> {
+    Ok(Affine {
+        a: combine_numeric(fc, InfixOp::Mul, factor, &affine.a)?,
+        b: combine_numeric(fc, InfixOp::Mul, factor, &affine.b)?,
+    })
+}
+
+/// Applies a numeric `Add`/`Sub`/`Mul`/`Div` to two plain values, the same
+/// way [`Runtime::eval_infix_op`] would for `Expression::InfixOp`.
+fn combine_numeric(fc: FC, op: InfixOp, lhs: &Value, rhs: &Value) -> Result<Value, EvalError> {
+    let unit = infix_unit(fc, op, lhs, rhs)?;
+    match (&lhs.kind, &rhs.kind) {
+        (ValueKind::Number(a), ValueKind::Number(b)) => {
+            let n = match op {
+                InfixOp::Add => a + b,
+                InfixOp::Sub => a - b,
+                InfixOp::Mul => a * b,
+                InfixOp::Div => a / b,
+                _ => unreachable!("combine_numeric is only called for Add/Sub/Mul/Div"),
+            };
+            Ok(Value {
+                kind: ValueKind::Number(n),
+                unit,
+            })
+        }
+        _ => Err(EvalError::InvalidInfixOperator(
+            fc,
+            op,
+            lhs.clone(),
+            rhs.clone(),
+        )),
+    }
+}
+
+fn negate_value(fc: FC, mut val: Value) -> Result<Value, EvalError> {
+    match &mut val.kind {
+        ValueKind::Number(n) => {
+            *n = -&*n;
+            Ok(val)
+        }
+        ValueKind::FunctionRef(_) | ValueKind::Bool(_) => Err(EvalError::InvalidPrefixOperator(
+            fc,
+            crate::syntax::PrefixOp::Neg,
+            val,
+        )),
+    }
+}
+
+fn invert_value(fc: FC, val: &Value) -> Result<Value, EvalError> {
+    match &val.kind {
+        ValueKind::Number(n) => Ok(Value {
+            kind: ValueKind::Number(n.inverse()),
+            unit: Unit::new().divide(&val.unit),
+        }),
+        kind => Err(EvalError::ExpectedNumber(
+            fc,
+            "equation solving",
+            kind.clone(),
+        )),
+    }
+}
+
+/// The source location of an expression, used to point at where a recursion
+/// limit was hit.
+fn expr_fc(expr: &Expression) -> FC {
+    match expr {
+        Expression::IntegerLit { fc, .. }
+        | Expression::FloatLit { fc, .. }
+        | Expression::MaybeUnitPrefix { fc, .. }
+        | Expression::Call { fc, .. }
+        | Expression::PrefixOp { fc, .. }
+        | Expression::InfixOp { fc, .. } => *fc,
+        Expression::Variable(id) => id.fc(),
+        Expression::UnitOf(fc, _) | Expression::Parenthesised(fc, _) => *fc,
+    }
+}
+
 fn apply_prefix(fc: FC, prefix: SiPrefix, mut val: Value) -> Result<Value, EvalError> {
     let kind = match (prefix, &val.kind) {
         (SiPrefix::Femto, ValueKind::Number(x)) => ValueKind::Number(x / 1_000_000_000_000_000u64),
@@ -282,12 +594,61 @@ fn apply_prefix(fc: FC, prefix: SiPrefix, mut val: Value) -> Result<Value, EvalE
         (SiPrefix::Peta, ValueKind::Number(x)) => {
             ValueKind::Number(x * BigDecimal::from(1_000_000_000_000_000u64))
         }
-        (_, ValueKind::FunctionRef(_)) => return Err(EvalError::InvalidSiPrefix(fc, prefix, val)),
+        (_, ValueKind::FunctionRef(_)) | (_, ValueKind::Bool(_)) => {
+            return Err(EvalError::InvalidSiPrefix(fc, prefix, val))
+        }
     };
     val.kind = kind;
     Ok(val)
 }
 
+/// Converts a `BigDecimal` exponent to an exact `Ratio` for integers, or an
+/// approximate one for fractional values (e.g. `0.5` -> `1/2`).
+fn bigdecimal_to_ratio(n: &BigDecimal) -> Option<Ratio<i64>> {
+    if n.is_integer() {
+        n.to_i64().map(Ratio::from_integer)
+    } else {
+        n.to_f64().and_then(Ratio::approximate_float)
+    }
+}
+
+/// Raises `a` to the (possibly fractional) power `b`. Integer exponents are
+/// computed exactly by repeated multiplication; fractional ones round-trip
+/// through `f64` via `exp((p/q) * ln(a))`.
+fn pow_numeric(fc: FC, a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, EvalError> {
+    let exponent =
+        bigdecimal_to_ratio(b).ok_or_else(|| EvalError::InvalidExponent(fc, b.clone()))?;
+
+    if exponent.is_integer() {
+        let pow = exponent.to_integer();
+
+        let mut res = BigDecimal::from(1);
+
+        for _ in 0..pow.abs() {
+            res = res * a;
+        }
+
+        if pow < 0 {
+            res = res.inverse();
+        }
+
+        Ok(res)
+    } else {
+        if a < &BigDecimal::from(0) {
+            return Err(EvalError::NegativeFractionalPower(fc, a.clone(), b.clone()));
+        }
+
+        let base = a
+            .to_f64()
+            .ok_or_else(|| EvalError::NumericConversion(fc, "pow"))?;
+        let p = *exponent.numer() as f64;
+        let q = *exponent.denom() as f64;
+        let res = ((p / q) * base.ln()).exp();
+
+        from_f64(fc, "pow", res)
+    }
+}
+
 fn infix_unit(fc: FC, op: InfixOp, lhs: &Value, rhs: &Value) -> Result<Unit, UnitError> {
     match op {
         InfixOp::Add | InfixOp::Sub | InfixOp::Mod => {
@@ -315,9 +676,11 @@ fn infix_unit(fc: FC, op: InfixOp, lhs: &Value, rhs: &Value) -> Result<Unit, Uni
                 ));
             }
             match &rhs.kind {
-                ValueKind::Number(n) if n.is_integer() => {
-                    let n = n.to_isize().unwrap();
-                    Ok(lhs.unit.pow(n))
+                ValueKind::Number(n) => {
+                    let exponent = bigdecimal_to_ratio(n).ok_or_else(|| {
+                        UnitError::InvalidPowerValue(fc, lhs.unit.clone(), rhs.kind.clone())
+                    })?;
+                    Ok(lhs.unit.pow(exponent))
                 }
                 _ => Err(UnitError::InvalidPowerValue(
                     fc,
@@ -326,9 +689,180 @@ fn infix_unit(fc: FC, op: InfixOp, lhs: &Value, rhs: &Value) -> Result<Unit, Uni
                 )),
             }
         }
-        InfixOp::Eq => todo!(),
-        InfixOp::Neq => todo!(),
-        InfixOp::Gt => todo!(),
+        InfixOp::Eq | InfixOp::Neq | InfixOp::Gt => {
+            if lhs.unit == rhs.unit {
+                Ok(Unit::new())
+            } else {
+                Err(UnitError::IncompatibleUnits(
+                    fc,
+                    op,
+                    lhs.unit.clone(),
+                    rhs.unit.clone(),
+                ))
+            }
+        }
+    }
+}
+
+fn builtin_functions() -> HashMap<Name, BuiltinFn> {
+    let mut builtins: HashMap<Name, BuiltinFn> = HashMap::new();
+    builtins.insert(Name::from("sqrt"), builtin_sqrt);
+    builtins.insert(Name::from("ln"), builtin_ln);
+    builtins.insert(Name::from("exp"), builtin_exp);
+    builtins.insert(Name::from("sin"), builtin_sin);
+    builtins.insert(Name::from("cos"), builtin_cos);
+    builtins.insert(Name::from("abs"), builtin_abs);
+    builtins.insert(Name::from("floor"), builtin_floor);
+    builtins.insert(Name::from("ceil"), builtin_ceil);
+    builtins.insert(Name::from("round"), builtin_round);
+    builtins
+}
+
+fn expect_arity(
+    fc: FC,
+    name: &'static str,
+    args: &[Value],
+    expected: usize,
+) -> Result<(), EvalError> {
+    if args.len() == expected {
+        Ok(())
+    } else {
+        Err(EvalError::BuiltinArityMismatch(
+            fc,
+            name,
+            expected,
+            args.len(),
+        ))
+    }
+}
+
+fn expect_number(fc: FC, name: &'static str, val: &Value) -> Result<f64, EvalError> {
+    match &val.kind {
+        ValueKind::Number(n) => n.to_f64().ok_or(EvalError::NumericConversion(fc, name)),
+        kind => Err(EvalError::ExpectedNumber(fc, name, kind.clone())),
+    }
+}
+
+fn expect_dimensionless(fc: FC, name: &'static str, val: &Value) -> Result<(), EvalError> {
+    if val.unit == Unit::new() {
+        Ok(())
+    } else {
+        Err(EvalError::ExpectedDimensionless(fc, name, val.unit.clone()))
+    }
+}
+
+// Transcendental functions are evaluated by round-tripping through `f64`
+// rather than in arbitrary precision, so results carry ordinary floating
+// point error.
+fn from_f64(fc: FC, name: &'static str, x: f64) -> Result<BigDecimal, EvalError> {
+    BigDecimal::from_f64(x).ok_or(EvalError::NumericConversion(fc, name))
+}
+
+fn builtin_sqrt(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "sqrt", args, 1)?;
+    let arg = &args[0];
+
+    let x = expect_number(fc, "sqrt", arg)?;
+    if x < 0.0 {
+        return match &arg.kind {
+            ValueKind::Number(n) => Err(EvalError::NegativeSqrt(fc, n.clone())),
+            _ => unreachable!("expect_number already rejected non-numbers"),
+        };
+    }
+
+    Ok(Value {
+        kind: ValueKind::Number(from_f64(fc, "sqrt", x.sqrt())?),
+        unit: arg.unit.pow(Ratio::new(1, 2)),
+    })
+}
+
+fn builtin_ln(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "ln", args, 1)?;
+    expect_dimensionless(fc, "ln", &args[0])?;
+    let x = expect_number(fc, "ln", &args[0])?;
+    Ok(Value {
+        kind: ValueKind::Number(from_f64(fc, "ln", x.ln())?),
+        unit: Unit::new(),
+    })
+}
+
+fn builtin_exp(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "exp", args, 1)?;
+    expect_dimensionless(fc, "exp", &args[0])?;
+    let x = expect_number(fc, "exp", &args[0])?;
+    Ok(Value {
+        kind: ValueKind::Number(from_f64(fc, "exp", x.exp())?),
+        unit: Unit::new(),
+    })
+}
+
+fn builtin_sin(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "sin", args, 1)?;
+    expect_dimensionless(fc, "sin", &args[0])?;
+    let x = expect_number(fc, "sin", &args[0])?;
+    Ok(Value {
+        kind: ValueKind::Number(from_f64(fc, "sin", x.sin())?),
+        unit: Unit::new(),
+    })
+}
+
+fn builtin_cos(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "cos", args, 1)?;
+    expect_dimensionless(fc, "cos", &args[0])?;
+    let x = expect_number(fc, "cos", &args[0])?;
+    Ok(Value {
+        kind: ValueKind::Number(from_f64(fc, "cos", x.cos())?),
+        unit: Unit::new(),
+    })
+}
+
+fn builtin_abs(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "abs", args, 1)?;
+    match &args[0].kind {
+        ValueKind::Number(n) => Ok(Value {
+            kind: ValueKind::Number(n.abs()),
+            unit: args[0].unit.clone(),
+        }),
+        kind => Err(EvalError::ExpectedNumber(fc, "abs", kind.clone())),
+    }
+}
+
+// floor/ceil/round are exact BigDecimal operations, unlike the transcendental
+// functions above: they round to an integer (down, up and half-up
+// respectively) via `with_scale_round`
+// rather than round-tripping through `f64`, so precision isn't lost for
+// values beyond f64's ~15-17 significant digits.
+
+fn builtin_floor(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "floor", args, 1)?;
+    match &args[0].kind {
+        ValueKind::Number(n) => Ok(Value {
+            kind: ValueKind::Number(n.with_scale_round(0, RoundingMode::Floor)),
+            unit: args[0].unit.clone(),
+        }),
+        kind => Err(EvalError::ExpectedNumber(fc, "floor", kind.clone())),
+    }
+}
+
+fn builtin_ceil(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "ceil", args, 1)?;
+    match &args[0].kind {
+        ValueKind::Number(n) => Ok(Value {
+            kind: ValueKind::Number(n.with_scale_round(0, RoundingMode::Ceiling)),
+            unit: args[0].unit.clone(),
+        }),
+        kind => Err(EvalError::ExpectedNumber(fc, "ceil", kind.clone())),
+    }
+}
+
+fn builtin_round(fc: FC, args: &[Value]) -> Result<Value, EvalError> {
+    expect_arity(fc, "round", args, 1)?;
+    match &args[0].kind {
+        ValueKind::Number(n) => Ok(Value {
+            kind: ValueKind::Number(n.with_scale_round(0, RoundingMode::HalfUp)),
+            unit: args[0].unit.clone(),
+        }),
+        kind => Err(EvalError::ExpectedNumber(fc, "round", kind.clone())),
     }
 }
 
@@ -365,6 +899,42 @@ pub enum EvalError {
     #[error("Invalid SI-prefix {:?} on value {:?}", .1, .2)]
     InvalidSiPrefix(FC, SiPrefix, Value),
 
+    #[error("Value is not callable: {:?}", .1)]
+    NotCallable(FC, Value),
+
+    #[error("Function {} expects {} argument(s), got {}", .1, .2, .3)]
+    ArityMismatch(FC, Name, usize, usize),
+
+    #[error("Builtin {} expects {} argument(s), got {}", .1, .2, .3)]
+    BuiltinArityMismatch(FC, &'static str, usize, usize),
+
+    #[error("Builtin {} expects a number, got {:?}", .1, .2)]
+    ExpectedNumber(FC, &'static str, ValueKind),
+
+    #[error("Builtin {} expects a dimensionless argument, got unit ({})", .1, .2)]
+    ExpectedDimensionless(FC, &'static str, Unit),
+
+    #[error("Builtin {} produced a value outside the range of a finite number", .1)]
+    NumericConversion(FC, &'static str),
+
+    #[error("sqrt of a negative number is not supported: {:?}", .1)]
+    NegativeSqrt(FC, BigDecimal),
+
+    #[error("Invalid exponent: {:?}", .1)]
+    InvalidExponent(FC, BigDecimal),
+
+    #[error("{:?} raised to the fractional power {:?} is not a real number", .1, .2)]
+    NegativeFractionalPower(FC, BigDecimal, BigDecimal),
+
+    #[error("Recursion limit exceeded")]
+    RecursionLimitExceeded(FC),
+
+    #[error("Equation is not linear in the unknown")]
+    NonlinearEquation(FC),
+
+    #[error("Equation has no unique solution")]
+    NoUniqueSolution(FC),
+
     #[error("Unit error: {}", .0)]
     UnitError(#[from] UnitError),
 }
@@ -376,4 +946,335 @@ pub enum UnitError {
 
     #[error("Invalid power on unit ({}): {}", .1, .2)]
     InvalidPowerValue(FC, Unit, ValueKind),
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn fc() -> FC {
+        FC::default()
+    }
+
+    #[test]
+    fn floor_preserves_precision_beyond_f64() {
+        let n = BigDecimal::from_str("123456789012345678901234567890.7").unwrap();
+        let val = Value {
+            kind: ValueKind::Number(n),
+            unit: Unit::new(),
+        };
+
+        let result = builtin_floor(fc(), &[val]).unwrap();
+
+        match result.kind {
+            ValueKind::Number(n) => {
+                assert_eq!(
+                    n,
+                    BigDecimal::from_str("123456789012345678901234567890").unwrap()
+                )
+            }
+            kind => panic!("expected a number, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn function_declaration_rejects_builtin_name_collision() {
+        let mut rt = Runtime::new();
+
+        let result = rt.eval_item(Item::FunctionDeclaration {
+            fc: fc(),
+            name: Name::from("sin"),
+            arg_names: vec![Name::from("x")],
+            rhs: Expression::Variable(Name::from("x")),
+        });
+
+        assert!(matches!(result, Err(ItemError::FunctionRedefined(_))));
+    }
+
+    #[test]
+    fn solve_for_accepts_unit_bearing_declaration() {
+        let mut rt = Runtime::new();
+        rt.units.insert(Name::from("meter"));
+
+        // `d = 5 * meter`: the unknown `d` doesn't appear on the right-hand
+        // side at all, so this is a plain declaration, not an equation. It
+        // used to fail because the affine solver compared the dimensionless
+        // zero placeholder against the unit-bearing right-hand side.
+        let rhs = Expression::InfixOp {
+            fc: fc(),
+            op: InfixOp::Mul,
+            lhs: Box::new(Expression::IntegerLit {
+                fc: fc(),
+                val: BigDecimal::from(5),
+            }),
+            rhs: Box::new(Expression::MaybeUnitPrefix {
+                fc: fc(),
+                name: Name::from("meter"),
+                full_name: Name::from("meter"),
+                prefix: SiPrefix::Kilo,
+            }),
+        };
+
+        let value = rt.solve_for(fc(), &Name::from("d"), &rhs).unwrap();
+
+        match value.kind {
+            ValueKind::Number(n) => assert_eq!(n, BigDecimal::from(5)),
+            kind => panic!("expected a number, got {:?}", kind),
+        }
+        assert!(value.unit == Unit::new_named(Name::from("meter")));
+    }
+
+    /// Builds a chain of ten functions `f0..f9`, each calling the next and
+    /// `f9` just returning its argument, so evaluating `f0(42)` performs
+    /// exactly ten nested calls.
+    fn ten_level_call_chain(max_depth: usize) -> (Runtime, Expression) {
+        let mut rt = Runtime::with_max_depth(max_depth);
+
+        for i in 0..9 {
+            let body = Expression::Call {
+                fc: fc(),
+                base: Box::new(Expression::Variable(Name::from(
+                    format!("f{}", i + 1).as_str(),
+                ))),
+                args: vec![Expression::Variable(Name::from("x"))],
+            };
+            rt.functions.insert(
+                Name::from(format!("f{}", i).as_str()),
+                (vec![Name::from("x")], body),
+            );
+        }
+        rt.functions.insert(
+            Name::from("f9"),
+            (vec![Name::from("x")], Expression::Variable(Name::from("x"))),
+        );
+
+        let call = Expression::Call {
+            fc: fc(),
+            base: Box::new(Expression::Variable(Name::from("f0"))),
+            args: vec![Expression::IntegerLit {
+                fc: fc(),
+                val: BigDecimal::from(42),
+            }],
+        };
+
+        (rt, call)
+    }
+
+    #[test]
+    fn ten_nested_calls_cost_ten_units_of_depth() {
+        let (rt, call) = ten_level_call_chain(10);
+        assert!(rt.eval_expr(&call).is_ok());
+    }
+
+    #[test]
+    fn ten_nested_calls_exceed_a_budget_of_nine() {
+        let (rt, call) = ten_level_call_chain(9);
+        assert!(matches!(
+            rt.eval_expr(&call),
+            Err(EvalError::RecursionLimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn calling_a_user_defined_function_succeeds() {
+        let mut rt = Runtime::new();
+        rt.functions.insert(
+            Name::from("double"),
+            (
+                vec![Name::from("x")],
+                Expression::InfixOp {
+                    fc: fc(),
+                    op: InfixOp::Mul,
+                    lhs: Box::new(Expression::Variable(Name::from("x"))),
+                    rhs: Box::new(Expression::IntegerLit {
+                        fc: fc(),
+                        val: BigDecimal::from(2),
+                    }),
+                },
+            ),
+        );
+
+        let call = Expression::Call {
+            fc: fc(),
+            base: Box::new(Expression::Variable(Name::from("double"))),
+            args: vec![Expression::IntegerLit {
+                fc: fc(),
+                val: BigDecimal::from(21),
+            }],
+        };
+
+        match rt.eval_expr(&call).unwrap().kind {
+            ValueKind::Number(n) => assert_eq!(n, BigDecimal::from(42)),
+            kind => panic!("expected a number, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn calling_a_function_with_the_wrong_arity_errors() {
+        let mut rt = Runtime::new();
+        rt.functions.insert(
+            Name::from("double"),
+            (vec![Name::from("x")], Expression::Variable(Name::from("x"))),
+        );
+
+        let call = Expression::Call {
+            fc: fc(),
+            base: Box::new(Expression::Variable(Name::from("double"))),
+            args: vec![],
+        };
+
+        assert!(matches!(
+            rt.eval_expr(&call),
+            Err(EvalError::ArityMismatch(_, _, 1, 0))
+        ));
+    }
+
+    #[test]
+    fn calling_a_non_function_value_errors() {
+        let mut rt = Runtime::new();
+        rt.variables.insert(
+            Name::from("x"),
+            Value {
+                kind: ValueKind::Number(BigDecimal::from(1)),
+                unit: Unit::new(),
+            },
+        );
+
+        let call = Expression::Call {
+            fc: fc(),
+            base: Box::new(Expression::Variable(Name::from("x"))),
+            args: vec![],
+        };
+
+        assert!(matches!(
+            rt.eval_expr(&call),
+            Err(EvalError::NotCallable(_, _))
+        ));
+    }
+
+    #[test]
+    fn equal_numbers_compare_true() {
+        let rt = Runtime::new();
+        let expr = Expression::InfixOp {
+            fc: fc(),
+            op: InfixOp::Eq,
+            lhs: Box::new(Expression::IntegerLit {
+                fc: fc(),
+                val: BigDecimal::from(5),
+            }),
+            rhs: Box::new(Expression::IntegerLit {
+                fc: fc(),
+                val: BigDecimal::from(5),
+            }),
+        };
+
+        match rt.eval_expr(&expr).unwrap().kind {
+            ValueKind::Bool(b) => assert!(b),
+            kind => panic!("expected a bool, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn comparing_mismatched_units_errors() {
+        let mut rt = Runtime::new();
+        rt.units.insert(Name::from("meter"));
+        rt.units.insert(Name::from("second"));
+
+        let expr = Expression::InfixOp {
+            fc: fc(),
+            op: InfixOp::Gt,
+            lhs: Box::new(Expression::MaybeUnitPrefix {
+                fc: fc(),
+                name: Name::from("meter"),
+                full_name: Name::from("meter"),
+                prefix: SiPrefix::Kilo,
+            }),
+            rhs: Box::new(Expression::MaybeUnitPrefix {
+                fc: fc(),
+                name: Name::from("second"),
+                full_name: Name::from("second"),
+                prefix: SiPrefix::Kilo,
+            }),
+        };
+
+        assert!(matches!(
+            rt.eval_expr(&expr),
+            Err(EvalError::UnitError(UnitError::IncompatibleUnits(
+                _,
+                InfixOp::Gt,
+                _,
+                _
+            )))
+        ));
+    }
+
+    #[test]
+    fn prefix_neg_rejects_bool_values() {
+        let rt = Runtime::new();
+        let bool_expr = Expression::InfixOp {
+            fc: fc(),
+            op: InfixOp::Eq,
+            lhs: Box::new(Expression::IntegerLit {
+                fc: fc(),
+                val: BigDecimal::from(5),
+            }),
+            rhs: Box::new(Expression::IntegerLit {
+                fc: fc(),
+                val: BigDecimal::from(5),
+            }),
+        };
+        let expr = Expression::PrefixOp {
+            fc: fc(),
+            op: crate::syntax::PrefixOp::Neg,
+            expr: Box::new(bool_expr),
+        };
+
+        assert!(matches!(
+            rt.eval_expr(&expr),
+            Err(EvalError::InvalidPrefixOperator(_, _, _))
+        ));
+    }
+
+    #[test]
+    fn si_prefix_rejects_bool_values() {
+        let val = Value {
+            kind: ValueKind::Bool(true),
+            unit: Unit::new(),
+        };
+
+        assert!(matches!(
+            apply_prefix(fc(), SiPrefix::Kilo, val),
+            Err(EvalError::InvalidSiPrefix(_, _, _))
+        ));
+    }
+
+    #[test]
+    fn ln_rejects_unit_bearing_argument() {
+        let val = Value {
+            kind: ValueKind::Number(BigDecimal::from(1)),
+            unit: Unit::new_named(Name::from("meter")),
+        };
+
+        assert!(matches!(
+            builtin_ln(fc(), &[val]),
+            Err(EvalError::ExpectedDimensionless(_, "ln", _))
+        ));
+    }
+
+    #[test]
+    fn abs_preserves_unit() {
+        let val = Value {
+            kind: ValueKind::Number(BigDecimal::from(-5)),
+            unit: Unit::new_named(Name::from("meter")),
+        };
+
+        let result = builtin_abs(fc(), &[val]).unwrap();
+
+        match result.kind {
+            ValueKind::Number(n) => assert_eq!(n, BigDecimal::from(5)),
+            kind => panic!("expected a number, got {:?}", kind),
+        }
+        assert!(result.unit == Unit::new_named(Name::from("meter")));
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use bigdecimal::BigDecimal;
+use num_rational::Ratio;
+
+use crate::syntax::Name;
+
+/// A unit of measurement, tracked as a map from base unit name to its
+/// exponent. Exponents are rationals rather than integers so that
+/// `Unit::pow` can represent fractional powers (e.g. `meter^(1/2)` for
+/// `sqrt(meter)`) without losing precision or rounding to the nearest
+/// integer. A unit with no entries is dimensionless.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Unit {
+    exponents: HashMap<Name, Ratio<i64>>,
+}
+
+impl Unit {
+    /// The dimensionless unit.
+    pub fn new() -> Self {
+        Unit {
+            exponents: HashMap::new(),
+        }
+    }
+
+    /// A single base unit, raised to the power of one.
+    pub fn new_named(name: Name) -> Self {
+        let mut exponents = HashMap::new();
+        exponents.insert(name, Ratio::from_integer(1));
+        Unit { exponents }
+    }
+
+    pub fn multiply(&self, other: &Unit) -> Unit {
+        self.combine(other, |a, b| a + b)
+    }
+
+    pub fn divide(&self, other: &Unit) -> Unit {
+        self.combine(other, |a, b| a - b)
+    }
+
+    /// Multiplies every base unit's exponent by `exponent`, so a fractional
+    /// `exponent` (e.g. `1/2`) cleanly halves a squared unit instead of
+    /// erroring, as integer-only exponents would require.
+    pub fn pow(&self, exponent: Ratio<i64>) -> Unit {
+        let exponents = self
+            .exponents
+            .iter()
+            .map(|(name, e)| (name.clone(), e * exponent))
+            .filter(|(_, e)| !e.is_integer() || e.to_integer() != 0)
+            .collect();
+        Unit { exponents }
+    }
+
+    fn combine(&self, other: &Unit, op: impl Fn(Ratio<i64>, Ratio<i64>) -> Ratio<i64>) -> Unit {
+        let mut exponents = self.exponents.clone();
+        for (name, &exp) in &other.exponents {
+            let combined = op(
+                exponents
+                    .get(name)
+                    .copied()
+                    .unwrap_or_else(|| Ratio::from_integer(0)),
+                exp,
+            );
+            exponents.insert(name.clone(), combined);
+        }
+        exponents.retain(|_, e| !e.is_integer() || e.to_integer() != 0);
+        Unit { exponents }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exponents.is_empty() {
+            return write!(f, "dimensionless");
+        }
+
+        let mut parts: Vec<String> = self
+            .exponents
+            .iter()
+            .map(|(name, exp)| {
+                if exp.is_integer() && exp.to_integer() == 1 {
+                    format!("{}", name)
+                } else {
+                    format!("{}^{}", name, exp)
+                }
+            })
+            .collect();
+        parts.sort();
+
+        write!(f, "{}", parts.join(" * "))
+    }
+}
+
+/// A value of the language: a number (carrying its `Unit`), a boolean, or a
+/// reference to a builtin/user-defined function.
+#[derive(Clone, Debug)]
+pub struct Value {
+    pub kind: ValueKind,
+    pub unit: Unit,
+}
+
+#[derive(Clone, Debug)]
+pub enum ValueKind {
+    Number(BigDecimal),
+    Bool(bool),
+    FunctionRef(Name),
+}